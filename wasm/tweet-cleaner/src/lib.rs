@@ -1,6 +1,7 @@
 use indexmap::IndexMap;
 use js_sys::Date;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use wasm_bindgen::prelude::*;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -65,6 +66,17 @@ struct IncomingTweet {
     lang: Option<String>,
     #[serde(default, alias = "isLiked", alias = "is_liked")]
     is_liked: Option<bool>,
+    #[serde(default, alias = "receivedAt", alias = "received_at")]
+    received_at: Option<Timestamp>,
+    #[serde(
+        default,
+        alias = "in_reply_to_status_id_str",
+        alias = "inReplyToId",
+        alias = "in_reply_to_status_id"
+    )]
+    in_reply_to_status_id: Option<String>,
+    #[serde(default, alias = "conversation_id", alias = "conversationId")]
+    conversation_id: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -101,12 +113,25 @@ struct NormalizedTweet {
     quoted_content: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     is_liked: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    received_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    in_reply_to_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    conversation_id: Option<String>,
 }
 
 #[derive(Clone, Debug)]
 struct NormalizedRecord {
     tweet: NormalizedTweet,
     ts_ms: Option<f64>,
+    received_ms: Option<f64>,
+}
+
+impl NormalizedRecord {
+    fn sort_key(&self) -> Option<f64> {
+        self.ts_ms.or(self.received_ms)
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -121,28 +146,65 @@ struct CleanStats {
 #[derive(Serialize, Deserialize, Debug, Clone)]
 struct CleanResult {
     tweets: Vec<NormalizedTweet>,
+    authors: Vec<NormalizedUser>,
     stats: CleanStats,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct NormalizedUser {
+    id: String,
+    name: String,
+    handle: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    avatar: Option<String>,
+}
+
+fn normalize_user(raw: &Value) -> Option<NormalizedUser> {
+    let user = raw.get("user")?;
+    let id = user.get("id_str").and_then(Value::as_str)?.trim();
+    let name = user.get("name").and_then(Value::as_str)?.trim();
+    let handle = user.get("screen_name").and_then(Value::as_str)?.trim();
+    if id.is_empty() || name.is_empty() || handle.is_empty() {
+        return None;
+    }
+
+    let avatar = user
+        .get("profile_image_url_https")
+        .or_else(|| user.get("profile_image_url"))
+        .and_then(Value::as_str)
+        .map(str::to_string);
+
+    Some(NormalizedUser {
+        id: id.to_string(),
+        name: name.to_string(),
+        handle: handle.to_string(),
+        avatar,
+    })
+}
+
 #[wasm_bindgen]
 pub fn clean_and_merge(
     existing: JsValue,
     incoming: JsValue,
     limit: Option<u32>,
 ) -> Result<JsValue, JsValue> {
-    let existing: Vec<IncomingTweet> =
+    let existing: Vec<Value> =
         serde_wasm_bindgen::from_value(existing).map_err(|e| JsValue::from(e.to_string()))?;
-    let incoming: Vec<IncomingTweet> =
+    let incoming: Vec<Value> =
         serde_wasm_bindgen::from_value(incoming).map_err(|e| JsValue::from(e.to_string()))?;
 
     let mut seen_existing = std::collections::HashSet::new();
     let mut records: IndexMap<String, NormalizedRecord> = IndexMap::new();
+    let mut authors: IndexMap<String, NormalizedUser> = IndexMap::new();
     let mut dropped = 0usize;
     let mut deduped = 0usize;
     let mut added = 0usize;
 
     for item in existing.iter() {
-        if let Some(normalized) = normalize_tweet(item) {
+        if let Some((normalized, user)) = normalize_tweet(item) {
+            if let Some(user) = user {
+                authors.insert(user.id.clone(), user);
+            }
             seen_existing.insert(normalized.tweet.id.clone());
             records.insert(normalized.tweet.id.clone(), normalized);
         } else {
@@ -151,9 +213,13 @@ pub fn clean_and_merge(
     }
 
     for item in incoming.iter() {
-        if let Some(normalized) = normalize_tweet(item) {
-            if records.contains_key(&normalized.tweet.id) {
+        if let Some((mut normalized, user)) = normalize_tweet(item) {
+            if let Some(user) = user {
+                authors.insert(user.id.clone(), user);
+            }
+            if let Some(original) = records.get(&normalized.tweet.id) {
                 deduped += 1;
+                preserve_received_at_on_remerge(&mut normalized, original);
             } else if seen_existing.contains(&normalized.tweet.id) {
                 deduped += 1;
             } else {
@@ -166,7 +232,11 @@ pub fn clean_and_merge(
     }
 
     let mut normalized: Vec<NormalizedRecord> = records.into_values().collect();
-    normalized.sort_by(|a, b| b.ts_ms.partial_cmp(&a.ts_ms).unwrap_or(std::cmp::Ordering::Equal));
+    normalized.sort_by(|a, b| {
+        b.sort_key()
+            .partial_cmp(&a.sort_key())
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
 
     let mut truncated = 0usize;
     if let Some(limit) = limit {
@@ -181,6 +251,7 @@ pub fn clean_and_merge(
     let total = tweets.len();
     let result = CleanResult {
         tweets,
+        authors: authors.into_values().collect(),
         stats: CleanStats {
             added,
             deduped,
@@ -193,38 +264,115 @@ pub fn clean_and_merge(
     serde_wasm_bindgen::to_value(&result).map_err(|e| JsValue::from(e.to_string()))
 }
 
-fn normalize_tweet(raw: &IncomingTweet) -> Option<NormalizedRecord> {
-    let id = pick_first(&[raw.id.as_deref()])?;
-    let url = pick_first(&[raw.url.as_deref()])?;
-    let text = pick_first(&[raw.text.as_deref()])?;
+// A re-merged tweet that still has no real `time` keeps the `received_at` stamped on
+// the first pass instead of being restamped with a fresh one.
+fn preserve_received_at_on_remerge(normalized: &mut NormalizedRecord, original: &NormalizedRecord) {
+    if normalized.ts_ms.is_none() {
+        normalized.received_ms = original.received_ms;
+        normalized.tweet.received_at = original.tweet.received_at.clone();
+    }
+}
+
+fn normalize_tweet(raw: &Value) -> Option<(NormalizedRecord, Option<NormalizedUser>)> {
+    let incoming: IncomingTweet = serde_json::from_value(raw.clone()).ok()?;
+    let user = normalize_user(raw);
+
+    let id = pick_first(&[incoming.id.as_deref()])?;
+    let url = pick_first(&[incoming.url.as_deref()])?;
+    let extracted_text = extract_full_text(raw);
+    let text = pick_first(&[extracted_text.as_deref(), incoming.text.as_deref()])?;
     if id.is_empty() || url.is_empty() || text.is_empty() {
         return None;
     }
 
-    let (time_iso, ts_ms) = normalize_time(raw);
+    let (time_iso, ts_ms) = normalize_time(&incoming);
+    let (received_at, received_ms) = if ts_ms.is_none() {
+        normalize_received_at(&incoming)
+    } else {
+        (None, None)
+    };
 
-    Some(NormalizedRecord {
+    let record = NormalizedRecord {
         ts_ms,
+        received_ms,
         tweet: NormalizedTweet {
-            id: id,
+            id,
             url,
             text,
             time: time_iso,
-            likes: raw.likes.as_ref().and_then(NumberLike::as_i64),
-            retweets: raw.retweets.as_ref().and_then(NumberLike::as_i64),
-            replies: raw.replies.as_ref().and_then(NumberLike::as_i64),
-            has_media: raw.has_media,
-            username: raw.username.clone().map(trim_owned),
-            user_id: raw.user_id.clone().map(trim_owned),
-            user_display_name: raw.user_display_name.clone().map(trim_owned),
-            user_avatar: raw.user_avatar.clone().map(trim_owned),
-            lang: raw.lang.clone().map(trim_owned),
-            views: raw.views.clone(),
-            is_reply: raw.is_reply,
-            quoted_content: raw.quoted_content.clone().map(trim_owned),
-            is_liked: raw.is_liked,
+            likes: incoming.likes.as_ref().and_then(NumberLike::as_i64),
+            retweets: incoming.retweets.as_ref().and_then(NumberLike::as_i64),
+            replies: incoming.replies.as_ref().and_then(NumberLike::as_i64),
+            has_media: incoming.has_media,
+            username: user
+                .as_ref()
+                .map(|u| u.handle.clone())
+                .or_else(|| incoming.username.clone())
+                .map(trim_owned),
+            user_id: user
+                .as_ref()
+                .map(|u| u.id.clone())
+                .or_else(|| incoming.user_id.clone())
+                .map(trim_owned),
+            user_display_name: user
+                .as_ref()
+                .map(|u| u.name.clone())
+                .or_else(|| incoming.user_display_name.clone())
+                .map(trim_owned),
+            user_avatar: user
+                .as_ref()
+                .and_then(|u| u.avatar.clone())
+                .or_else(|| incoming.user_avatar.clone())
+                .map(trim_owned),
+            lang: incoming.lang.clone().map(trim_owned),
+            views: incoming.views.clone(),
+            is_reply: incoming.is_reply,
+            quoted_content: extract_quoted_text(raw)
+                .or_else(|| incoming.quoted_content.clone())
+                .map(trim_owned),
+            is_liked: incoming.is_liked,
+            received_at,
+            in_reply_to_id: incoming.in_reply_to_status_id.clone().map(trim_owned),
+            conversation_id: incoming.conversation_id.clone().map(trim_owned),
         },
-    })
+    };
+
+    Some((record, user))
+}
+
+fn extract_full_text(raw: &Value) -> Option<String> {
+    if let Some(retweeted) = raw.get("retweeted_status").filter(|v| !v.is_null()) {
+        let inner = extract_full_text(retweeted)?;
+        let handle = retweeted
+            .get("user")
+            .and_then(|u| u.get("screen_name"))
+            .and_then(Value::as_str)
+            .unwrap_or("");
+        return Some(format!("RT @{}: {}", handle, inner));
+    }
+
+    let truncated = raw
+        .get("truncated")
+        .and_then(Value::as_bool)
+        .unwrap_or(false);
+    if truncated {
+        if let Some(text) = raw
+            .get("extended_tweet")
+            .and_then(|e| e.get("full_text"))
+            .and_then(Value::as_str)
+        {
+            return Some(text.to_string());
+        }
+    }
+
+    raw.get("full_text")
+        .and_then(Value::as_str)
+        .or_else(|| raw.get("text").and_then(Value::as_str))
+        .map(str::to_string)
+}
+
+fn extract_quoted_text(raw: &Value) -> Option<String> {
+    raw.get("quoted_status").and_then(extract_full_text)
 }
 
 fn normalize_time(raw: &IncomingTweet) -> (Option<String>, Option<f64>) {
@@ -233,6 +381,16 @@ fn normalize_time(raw: &IncomingTweet) -> (Option<String>, Option<f64>) {
     (iso, ts_ms)
 }
 
+fn normalize_received_at(raw: &IncomingTweet) -> (Option<String>, Option<f64>) {
+    let ms = raw
+        .received_at
+        .as_ref()
+        .and_then(timestamp_to_ms)
+        .unwrap_or_else(Date::now);
+    let iso = Date::new(&JsValue::from_f64(ms)).to_iso_string().into();
+    (Some(iso), Some(ms))
+}
+
 fn timestamp_to_ms(ts: &Timestamp) -> Option<f64> {
     match ts {
         Timestamp::Float(v) => Some(normalize_numeric_time(*v)),
@@ -259,6 +417,256 @@ fn normalize_numeric_time(value: f64) -> f64 {
     }
 }
 
+#[derive(Debug, Clone)]
+enum TwitterEvent {
+    Deleted { tweet_id: String },
+    Fav { tweet_id: String },
+    Unfav { tweet_id: String },
+    Retweeted { tweet_id: String },
+    Followed { user_id: String },
+    Unfollowed { user_id: String },
+}
+
+impl TwitterEvent {
+    fn from_json(raw: &Value) -> Option<TwitterEvent> {
+        let event = raw.get("event").and_then(Value::as_str)?;
+        let target_id = || {
+            raw.get("target_object")
+                .and_then(|v| v.get("id_str"))
+                .and_then(Value::as_str)
+                .map(str::to_string)
+        };
+        let source_id = || {
+            raw.get("source")
+                .and_then(|v| v.get("id_str"))
+                .and_then(Value::as_str)
+                .map(str::to_string)
+        };
+
+        match event {
+            "delete" => target_id().map(|tweet_id| TwitterEvent::Deleted { tweet_id }),
+            "favorite" => target_id().map(|tweet_id| TwitterEvent::Fav { tweet_id }),
+            "unfavorite" => target_id().map(|tweet_id| TwitterEvent::Unfav { tweet_id }),
+            "retweet" => target_id().map(|tweet_id| TwitterEvent::Retweeted { tweet_id }),
+            "follow" => source_id().map(|user_id| TwitterEvent::Followed { user_id }),
+            "unfollow" => source_id().map(|user_id| TwitterEvent::Unfollowed { user_id }),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct EventStats {
+    deleted: usize,
+    fav_toggled: usize,
+    ignored: usize,
+    total: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct ApplyEventsResult {
+    tweets: Vec<NormalizedTweet>,
+    stats: EventStats,
+}
+
+fn apply_events(existing: &[Value], events: &[Value]) -> (Vec<NormalizedRecord>, EventStats) {
+    let mut records: IndexMap<String, NormalizedRecord> = IndexMap::new();
+    for item in existing.iter() {
+        if let Some((normalized, _user)) = normalize_tweet(item) {
+            records.insert(normalized.tweet.id.clone(), normalized);
+        }
+    }
+
+    let mut deleted = 0usize;
+    let mut fav_toggled = 0usize;
+    let mut ignored = 0usize;
+
+    for raw_event in events.iter() {
+        match TwitterEvent::from_json(raw_event) {
+            Some(TwitterEvent::Deleted { tweet_id }) => {
+                if records.shift_remove(&tweet_id).is_some() {
+                    deleted += 1;
+                } else {
+                    ignored += 1;
+                }
+            }
+            Some(TwitterEvent::Fav { tweet_id }) => match records.get_mut(&tweet_id) {
+                Some(record) => {
+                    record.tweet.is_liked = Some(true);
+                    fav_toggled += 1;
+                }
+                None => ignored += 1,
+            },
+            Some(TwitterEvent::Unfav { tweet_id }) => match records.get_mut(&tweet_id) {
+                Some(record) => {
+                    record.tweet.is_liked = Some(false);
+                    fav_toggled += 1;
+                }
+                None => ignored += 1,
+            },
+            Some(TwitterEvent::Retweeted { .. })
+            | Some(TwitterEvent::Followed { .. })
+            | Some(TwitterEvent::Unfollowed { .. })
+            | None => ignored += 1,
+        }
+    }
+
+    let mut normalized: Vec<NormalizedRecord> = records.into_values().collect();
+    normalized.sort_by(|a, b| {
+        b.sort_key()
+            .partial_cmp(&a.sort_key())
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    let total = normalized.len();
+
+    (
+        normalized,
+        EventStats {
+            deleted,
+            fav_toggled,
+            ignored,
+            total,
+        },
+    )
+}
+
+#[wasm_bindgen]
+pub fn apply_twitter_events(existing: JsValue, events: JsValue) -> Result<JsValue, JsValue> {
+    let existing: Vec<Value> =
+        serde_wasm_bindgen::from_value(existing).map_err(|e| JsValue::from(e.to_string()))?;
+    let events: Vec<Value> =
+        serde_wasm_bindgen::from_value(events).map_err(|e| JsValue::from(e.to_string()))?;
+
+    let (normalized, stats) = apply_events(&existing, &events);
+    let tweets: Vec<NormalizedTweet> = normalized.into_iter().map(|r| r.tweet).collect();
+
+    let result = ApplyEventsResult { tweets, stats };
+    serde_wasm_bindgen::to_value(&result).map_err(|e| JsValue::from(e.to_string()))
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct ThreadView {
+    root: String,
+    tweets: Vec<NormalizedTweet>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct ThreadResult {
+    threads: Vec<ThreadView>,
+    orphans: Vec<String>,
+    cyclic: Vec<String>,
+}
+
+#[wasm_bindgen]
+pub fn build_threads(tweets: JsValue) -> Result<JsValue, JsValue> {
+    let tweets: Vec<NormalizedTweet> =
+        serde_wasm_bindgen::from_value(tweets).map_err(|e| JsValue::from(e.to_string()))?;
+
+    let by_id: IndexMap<String, NormalizedTweet> = tweets
+        .iter()
+        .cloned()
+        .map(|tweet| (tweet.id.clone(), tweet))
+        .collect();
+
+    let mut children: IndexMap<String, Vec<String>> = IndexMap::new();
+    let mut roots: Vec<String> = Vec::new();
+    let mut orphans: Vec<String> = Vec::new();
+
+    for tweet in &tweets {
+        match &tweet.in_reply_to_id {
+            Some(parent_id) if by_id.contains_key(parent_id) => {
+                children.entry(parent_id.clone()).or_default().push(tweet.id.clone());
+            }
+            Some(_) => {
+                roots.push(tweet.id.clone());
+                orphans.push(tweet.id.clone());
+            }
+            None => roots.push(tweet.id.clone()),
+        }
+    }
+
+    for sibling_ids in children.values_mut() {
+        sibling_ids.sort_by(|a, b| {
+            tweet_sort_key(&by_id[a])
+                .partial_cmp(&tweet_sort_key(&by_id[b]))
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+    }
+
+    let mut visited: std::collections::HashSet<String> = std::collections::HashSet::new();
+    let mut threads: Vec<ThreadView> = roots
+        .into_iter()
+        .map(|root| {
+            let mut ordered_ids = Vec::new();
+            collect_thread_ids(&root, &children, &mut visited, &mut ordered_ids);
+            ThreadView {
+                root,
+                tweets: ordered_ids
+                    .into_iter()
+                    .filter_map(|id| by_id.get(&id).cloned())
+                    .collect(),
+            }
+        })
+        .collect();
+
+    // A reply chain that loops back on itself is never reached from a real root.
+    let mut cyclic: Vec<String> = Vec::new();
+    for tweet in &tweets {
+        if visited.contains(&tweet.id) {
+            continue;
+        }
+        cyclic.push(tweet.id.clone());
+        let mut ordered_ids = Vec::new();
+        collect_thread_ids(&tweet.id, &children, &mut visited, &mut ordered_ids);
+        threads.push(ThreadView {
+            root: tweet.id.clone(),
+            tweets: ordered_ids
+                .into_iter()
+                .filter_map(|id| by_id.get(&id).cloned())
+                .collect(),
+        });
+    }
+
+    let result = ThreadResult { threads, orphans, cyclic };
+    serde_wasm_bindgen::to_value(&result).map_err(|e| JsValue::from(e.to_string()))
+}
+
+// Depth-first so a branch's descendants stay grouped together; `visited` also guards
+// against a reply chain that cycles back on itself. Iterative (heap-backed stack) rather
+// than recursive so a long reply chain can't blow the call stack.
+fn collect_thread_ids(
+    id: &str,
+    children: &IndexMap<String, Vec<String>>,
+    visited: &mut std::collections::HashSet<String>,
+    ordered_ids: &mut Vec<String>,
+) {
+    if !visited.insert(id.to_string()) {
+        return;
+    }
+
+    let mut stack = vec![id.to_string()];
+    while let Some(current) = stack.pop() {
+        ordered_ids.push(current.clone());
+        if let Some(child_ids) = children.get(&current) {
+            for child_id in child_ids.iter().rev() {
+                if visited.insert(child_id.clone()) {
+                    stack.push(child_id.clone());
+                }
+            }
+        }
+    }
+}
+
+fn tweet_sort_key(tweet: &NormalizedTweet) -> f64 {
+    tweet
+        .time
+        .as_deref()
+        .or(tweet.received_at.as_deref())
+        .map(|iso| Date::new(&JsValue::from_str(iso)).get_time())
+        .filter(|ms| !ms.is_nan())
+        .unwrap_or(f64::MAX)
+}
+
 fn pick_first(options: &[Option<&str>]) -> Option<String> {
     for opt in options {
         if let Some(v) = opt {
@@ -274,3 +682,140 @@ fn pick_first(options: &[Option<&str>]) -> Option<String> {
 fn trim_owned(value: String) -> String {
     value.trim().to_string()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn extract_full_text_unrolls_retweet_of_quote() {
+        let raw = json!({
+            "text": "ignored",
+            "retweeted_status": {
+                "text": "rt body",
+                "user": {"screen_name": "alice"},
+                "quoted_status": {"full_text": "original"},
+            },
+        });
+        assert_eq!(extract_full_text(&raw).as_deref(), Some("RT @alice: rt body"));
+    }
+
+    #[test]
+    fn extract_full_text_ignores_a_null_retweeted_status() {
+        let raw = json!({"text": "plain text", "retweeted_status": null});
+        assert_eq!(extract_full_text(&raw).as_deref(), Some("plain text"));
+    }
+
+    #[test]
+    fn extract_full_text_falls_back_when_extended_tweet_is_missing() {
+        let raw = json!({"truncated": true, "text": "short"});
+        assert_eq!(extract_full_text(&raw).as_deref(), Some("short"));
+    }
+
+    #[test]
+    fn normalize_user_reads_the_nested_user_object() {
+        let raw = json!({
+            "user": {
+                "id_str": "123",
+                "name": "Alice",
+                "screen_name": "alice",
+                "profile_image_url_https": "https://example.com/a.jpg",
+            },
+        });
+        let user = normalize_user(&raw).expect("user should parse");
+        assert_eq!(user.id, "123");
+        assert_eq!(user.handle, "alice");
+        assert_eq!(user.avatar.as_deref(), Some("https://example.com/a.jpg"));
+    }
+
+    #[test]
+    fn apply_events_deletes_a_tweet() {
+        let existing = vec![json!({"id": "1", "url": "https://x.com/1", "text": "hello", "time": 1_700_000_000})];
+        let events = vec![json!({"event": "delete", "target_object": {"id_str": "1"}})];
+
+        let (records, stats) = apply_events(&existing, &events);
+
+        assert!(records.is_empty());
+        assert_eq!(stats.deleted, 1);
+        assert_eq!(stats.ignored, 0);
+    }
+
+    #[test]
+    fn apply_events_toggles_is_liked() {
+        let existing = vec![json!({"id": "1", "url": "https://x.com/1", "text": "hello", "time": 1_700_000_000})];
+        let events = vec![
+            json!({"event": "favorite", "target_object": {"id_str": "1"}}),
+            json!({"event": "unfavorite", "target_object": {"id_str": "1"}}),
+        ];
+
+        let (records, stats) = apply_events(&existing, &events);
+
+        assert_eq!(records[0].tweet.is_liked, Some(false));
+        assert_eq!(stats.fav_toggled, 2);
+    }
+
+    #[test]
+    fn apply_events_counts_unrecognized_events_as_ignored() {
+        let existing = vec![json!({"id": "1", "url": "https://x.com/1", "text": "hello", "time": 1_700_000_000})];
+        let events = vec![json!({"event": "something_new", "target_object": {"id_str": "1"}})];
+
+        let (_records, stats) = apply_events(&existing, &events);
+
+        assert_eq!(stats.ignored, 1);
+        assert_eq!(stats.deleted, 0);
+        assert_eq!(stats.fav_toggled, 0);
+    }
+
+    #[test]
+    fn remerging_a_timeless_tweet_keeps_the_first_received_at() {
+        let raw = json!({"id": "1", "url": "https://x.com/1", "text": "hello"});
+        let (first, _) = normalize_tweet(&raw).expect("should normalize");
+        let (mut second, _) = normalize_tweet(&raw).expect("should normalize");
+
+        assert!(first.ts_ms.is_none());
+        assert!(first.received_ms.is_some());
+
+        preserve_received_at_on_remerge(&mut second, &first);
+
+        assert_eq!(second.received_ms, first.received_ms);
+        assert_eq!(second.tweet.received_at, first.tweet.received_at);
+    }
+
+    #[test]
+    fn collect_thread_ids_stops_at_a_cycle() {
+        let mut children: IndexMap<String, Vec<String>> = IndexMap::new();
+        children.insert("a".to_string(), vec!["b".to_string()]);
+        children.insert("b".to_string(), vec!["a".to_string()]);
+
+        let mut visited = std::collections::HashSet::new();
+        let mut ordered = Vec::new();
+        collect_thread_ids("a", &children, &mut visited, &mut ordered);
+
+        assert_eq!(ordered, vec!["a".to_string(), "b".to_string()]);
+    }
+
+    #[test]
+    fn collect_thread_ids_walks_each_branch_to_its_end_before_the_next_sibling() {
+        let mut children: IndexMap<String, Vec<String>> = IndexMap::new();
+        children.insert(
+            "root".to_string(),
+            vec!["child1".to_string(), "child2".to_string()],
+        );
+        children.insert("child1".to_string(), vec!["grandchild".to_string()]);
+
+        let mut visited = std::collections::HashSet::new();
+        let mut ordered = Vec::new();
+        collect_thread_ids("root", &children, &mut visited, &mut ordered);
+
+        assert_eq!(
+            ordered,
+            vec![
+                "root".to_string(),
+                "child1".to_string(),
+                "grandchild".to_string(),
+                "child2".to_string(),
+            ]
+        );
+    }
+}